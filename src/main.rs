@@ -1,3 +1,4 @@
+use std::collections::VecDeque;
 use std::sync::mpsc::Receiver;
 
 use color_eyre::{
@@ -6,11 +7,11 @@ use color_eyre::{
 };
 use ratatui::{
     buffer::Buffer,
-    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind},
+    crossterm::event::{Event, KeyCode, KeyEvent, KeyEventKind, MouseButton, MouseEvent, MouseEventKind},
     layout::{Alignment, Rect},
     style::{Style, Stylize},
     symbols::{self, border},
-    text::{Line, Text},
+    text::Line,
     widgets::{
         block::{Position, Title}, Axis, Block, Chart, Dataset, GraphType, Widget
     },
@@ -21,12 +22,40 @@ use vec2::Vec2;
 mod errors;
 mod tui;
 mod events;
+mod quadtree;
+mod scenario;
 mod vec2;
 
 #[derive(Clone, Copy, Debug, Default)]
 pub struct Planet {
     pos: Vec2,
     vel: Vec2,
+    mass: f64,
+}
+
+impl Planet {
+    /// treats the body as a uniform-density sphere, so its collision radius
+    /// grows with the cube root of its mass
+    fn radius(&self) -> f64 {
+        self.mass.cbrt()
+    }
+}
+
+/// the visible region of simulation space, expressed as a center and a
+/// half-width/half-height (the viewport is always square)
+#[derive(Clone, Copy, Debug)]
+pub struct Camera {
+    center: Vec2,
+    half_extent: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Camera {
+            center: Vec2::default(),
+            half_extent: 10.0,
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -34,6 +63,45 @@ pub struct App {
     rx: Receiver<events::Event>,
     planets: Vec<Planet>,
     exit: bool,
+    /// gravitational constant
+    g: f64,
+    /// physics timestep, in simulated seconds
+    dt: f64,
+    /// softening length that keeps the force finite as bodies approach each other
+    epsilon: f64,
+    /// when true, `DrawInterrupt` still redraws but skips `run_physics`
+    paused: bool,
+    /// number of physics steps run per `DrawInterrupt` while unpaused
+    speed: u32,
+    /// when true, overlapping bodies are merged into one after each step;
+    /// when false they are left to pass through each other (elastic bounce
+    /// is a possible future alternative)
+    merge_collisions: bool,
+    /// the viewport used to compute chart bounds
+    camera: Camera,
+    /// when true, `camera` is recomputed every frame to enclose all planets;
+    /// when false the camera only moves in response to pan/zoom key presses
+    auto_fit: bool,
+    /// when true, accelerations are approximated with a Barnes–Hut quadtree
+    /// instead of the exact O(n²) double loop, trading accuracy for the
+    /// ability to simulate thousands of bodies
+    barnes_hut: bool,
+    /// Barnes–Hut opening angle: nodes with width/distance below this are
+    /// treated as a single point mass
+    theta: f64,
+    /// the screen area the chart was last drawn into, used to map mouse
+    /// clicks back into simulation coordinates
+    plot_area: Rect,
+    /// index into `planets` of the body clicked by the user, if any
+    selected: Option<usize>,
+    /// when true and a body is selected, the auto-fit camera centers on it
+    /// every frame instead of on the bounding-box centroid
+    follow_selected: bool,
+    /// recent positions of each planet, indexed in parallel with `planets`,
+    /// used to draw fading orbital trails
+    trails: Vec<VecDeque<Vec2>>,
+    /// maximum number of positions kept per trail
+    trail_length: usize,
 }
 
 impl App {
@@ -46,8 +114,89 @@ impl App {
         Ok(())
     }
 
-    fn render_frame(&self, frame: &mut Frame) {
-        frame.render_widget(self, frame.size());
+    fn render_frame(&mut self, frame: &mut Frame) {
+        if self.auto_fit {
+            self.fit_camera();
+        }
+
+        let area = frame.size();
+        // mirrors Block::bordered()'s 1-cell margin, plus the left-hand
+        // gutter Chart reserves for the y-axis tick labels drawn in the
+        // Widget impl below; the x-axis labels don't reserve a gutter of
+        // their own so the height needs no equivalent adjustment
+        let y_gutter = self.y_axis_gutter_width();
+        self.plot_area = Rect {
+            x: area.x + 1 + y_gutter,
+            y: area.y + 1,
+            width: area.width.saturating_sub(2 + y_gutter),
+            height: area.height.saturating_sub(2),
+        };
+
+        frame.render_widget(&*self, area);
+    }
+
+    /// the three labels drawn along the y-axis: its min, center, and max
+    fn y_axis_labels(&self) -> [String; 3] {
+        let y_min = self.camera.center.y - self.camera.half_extent;
+        let y_max = self.camera.center.y + self.camera.half_extent;
+        [
+            format!("{y_min:.1}"),
+            format!("{:.1}", self.camera.center.y),
+            format!("{y_max:.1}"),
+        ]
+    }
+
+    /// width Chart reserves to the left of the plot for the y-axis labels
+    fn y_axis_gutter_width(&self) -> u16 {
+        self.y_axis_labels()
+            .iter()
+            .map(|label| label.len() as u16)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    /// sets `camera` to enclose the bounding box of every planet, with a small margin
+    fn fit_camera(&mut self) {
+        let Some(first) = self.planets.first() else {
+            return;
+        };
+
+        let (mut min_x, mut max_x) = (first.pos.x, first.pos.x);
+        let (mut min_y, mut max_y) = (first.pos.y, first.pos.y);
+        for planet in &self.planets[1..] {
+            min_x = min_x.min(planet.pos.x);
+            max_x = max_x.max(planet.pos.x);
+            min_y = min_y.min(planet.pos.y);
+            max_y = max_y.max(planet.pos.y);
+        }
+
+        let center = Vec2 {
+            x: (min_x + max_x) / 2.0,
+            y: (min_y + max_y) / 2.0,
+        };
+        let half_extent = ((max_x - min_x).max(max_y - min_y) / 2.0 * 1.2).max(1.0);
+
+        let center = match self.selected.filter(|_| self.follow_selected) {
+            Some(i) => self.planets.get(i).map(|p| p.pos).unwrap_or(center),
+            None => center,
+        };
+
+        self.camera = Camera { center, half_extent };
+    }
+
+    /// nudges the manual camera in simulation space; panning drops out of auto-fit
+    fn pan(&mut self, dx: f64, dy: f64) {
+        self.auto_fit = false;
+        let step = self.camera.half_extent * 0.1;
+        self.camera.center.x += dx * step;
+        self.camera.center.y += dy * step;
+    }
+
+    /// scales the manual camera's half-extent; zooming drops out of auto-fit
+    fn zoom(&mut self, factor: f64) {
+        self.auto_fit = false;
+        self.camera.half_extent *= factor;
     }
 
     /// updates the application's state based on user input
@@ -60,8 +209,16 @@ impl App {
             events::Event::Console(Event::Key(key_event)) if key_event.kind == KeyEventKind::Press => self
                 .handle_key_event(key_event)
                 .wrap_err_with(|| format!("handling key event failed:\n{key_event:#?}")),
+            events::Event::Console(Event::Mouse(mouse_event)) => self
+                .handle_mouse_event(mouse_event)
+                .wrap_err_with(|| format!("handling mouse event failed:\n{mouse_event:#?}")),
             events::Event::DrawInterrupt => {
-                self.run_physics()
+                if !self.paused {
+                    for _ in 0..self.speed {
+                        self.run_physics()?;
+                    }
+                }
+                Ok(())
             }
             _ => Ok(()),
         }?;
@@ -69,34 +226,206 @@ impl App {
         Ok(())
     }
 
+    /// advances every body by one timestep using a symplectic velocity-Verlet
+    /// integration of Newtonian gravity, which keeps total energy roughly
+    /// conserved over long runs unlike the semi-implicit Euler step this replaced
     fn run_physics(&mut self) -> Result<()> {
-        for i1 in 0..self.planets.len() {
-            let mut acc = Vec2::default();
-            for i2 in 0..self.planets.len() {
-                if i1 == i2 {
-                    continue;
-                }
+        let dt = self.dt;
+        let old_acc = self.compute_accelerations();
 
-                let direction = (self.planets[i2].pos - self.planets[i1].pos).normalized();
-                let dist = direction.dot(&direction);
-                let force = dist.powi(-2) * 0.01;
-                acc += direction * force;
-            }
+        for (planet, acc) in self.planets.iter_mut().zip(&old_acc) {
+            planet.pos = planet.pos + planet.vel * dt + *acc * (0.5 * dt * dt);
+        }
+
+        let new_acc = self.compute_accelerations();
 
-            self.planets[i1].vel += acc;
-            self.planets[i1].pos = self.planets[i1].pos + self.planets[i1].vel;
+        for ((planet, old_acc), new_acc) in self.planets.iter_mut().zip(&old_acc).zip(&new_acc) {
+            planet.vel += (*old_acc + *new_acc) * (0.5 * dt);
         }
+
+        self.handle_collisions();
+        self.push_trails();
+
         Ok(())
     }
 
+    /// records the current position of every planet into its trail, keeping
+    /// at most `trail_length` entries per body
+    fn push_trails(&mut self) {
+        self.trails.resize_with(self.planets.len(), VecDeque::new);
+
+        for (trail, planet) in self.trails.iter_mut().zip(&self.planets) {
+            trail.push_back(planet.pos);
+            while trail.len() > self.trail_length {
+                trail.pop_front();
+            }
+        }
+    }
+
+    /// merges any pair of bodies whose discs overlap, conserving momentum and
+    /// mass, to avoid the numerical blow-ups close passes cause under 1/r²
+    fn handle_collisions(&mut self) {
+        if !self.merge_collisions {
+            return;
+        }
+
+        let mut i = 0;
+        while i < self.planets.len() {
+            let mut merged_any = false;
+            let mut j = i + 1;
+            while j < self.planets.len() {
+                let p1 = self.planets[i];
+                let p2 = self.planets[j];
+                let r = p2.pos - p1.pos;
+                let dist = r.dot(&r).sqrt();
+
+                if dist < p1.radius() + p2.radius() {
+                    let total_mass = p1.mass + p2.mass;
+                    self.planets[i] = Planet {
+                        pos: (p1.pos * p1.mass + p2.pos * p2.mass) / total_mass,
+                        vel: (p1.vel * p1.mass + p2.vel * p2.mass) / total_mass,
+                        mass: total_mass,
+                    };
+                    self.planets.remove(j);
+                    if let Some(trail) = self.trails.get_mut(i) {
+                        trail.clear();
+                    }
+                    if j < self.trails.len() {
+                        self.trails.remove(j);
+                    }
+                    // `j` no longer exists and everything past it shifted down
+                    // one slot, so remap (or drop) the selected index to keep
+                    // it pointing at the same body
+                    self.selected = self.selected.and_then(|sel| match sel.cmp(&j) {
+                        std::cmp::Ordering::Equal => Some(i),
+                        std::cmp::Ordering::Greater => Some(sel - 1),
+                        std::cmp::Ordering::Less => Some(sel),
+                    });
+                    merged_any = true;
+                } else {
+                    j += 1;
+                }
+            }
+            if !merged_any {
+                i += 1;
+            }
+        }
+    }
+
+    /// computes `a_i = Σ_{j≠i} G·m_j·(r_j−r_i) / (|r_j−r_i|² + ε²)^1.5` for every
+    /// body, either exactly or via the Barnes–Hut approximation depending on
+    /// `barnes_hut`
+    fn compute_accelerations(&self) -> Vec<Vec2> {
+        let eps2 = self.epsilon * self.epsilon;
+
+        if self.barnes_hut {
+            let bodies = self
+                .planets
+                .iter()
+                .map(|p| quadtree::Body { pos: p.pos, mass: p.mass })
+                .collect::<Vec<_>>();
+            return quadtree::accelerations(&bodies, self.g, eps2, self.theta);
+        }
+
+        self.planets
+            .iter()
+            .map(|p1| {
+                let mut acc = Vec2::default();
+                for p2 in &self.planets {
+                    let r = p2.pos - p1.pos;
+                    let dist2 = r.dot(&r);
+                    if dist2 == 0.0 {
+                        continue;
+                    }
+                    let inv_dist3 = (dist2 + eps2).powf(-1.5);
+                    acc += r * (self.g * p2.mass * inv_dist3);
+                }
+                acc
+            })
+            .collect()
+    }
+
     fn handle_key_event(&mut self, key_event: KeyEvent) -> Result<()> {
         match key_event.code {
             KeyCode::Char('q') => self.exit(),
+            KeyCode::Char(' ') => self.paused = !self.paused,
+            KeyCode::Char('s') | KeyCode::Char('S') | KeyCode::Char('.') => {
+                if self.paused {
+                    self.run_physics()?;
+                }
+            }
+            KeyCode::Char('+') => self.speed += 1,
+            KeyCode::Char('-') => self.speed = self.speed.saturating_sub(1).max(1),
+            KeyCode::Char('a') | KeyCode::Char('A') => self.auto_fit = !self.auto_fit,
+            KeyCode::Left => self.pan(-1.0, 0.0),
+            KeyCode::Right => self.pan(1.0, 0.0),
+            KeyCode::Up => self.pan(0.0, 1.0),
+            KeyCode::Down => self.pan(0.0, -1.0),
+            KeyCode::PageUp => self.zoom(0.9),
+            KeyCode::PageDown => self.zoom(1.1),
+            KeyCode::Char('b') | KeyCode::Char('B') => self.barnes_hut = !self.barnes_hut,
+            KeyCode::Char('f') | KeyCode::Char('F') => self.follow_selected = !self.follow_selected,
+            KeyCode::Char('c') | KeyCode::Char('C') => self.trails.iter_mut().for_each(VecDeque::clear),
+            KeyCode::Char('[') => self.trail_length = self.trail_length.saturating_sub(20),
+            KeyCode::Char(']') => self.trail_length += 20,
             _ => {}
         }
         Ok(())
     }
 
+    /// requires mouse capture to be enabled at terminal init so crossterm
+    /// reports `Event::Mouse` instead of consuming clicks
+    fn handle_mouse_event(&mut self, mouse_event: MouseEvent) -> Result<()> {
+        if let MouseEventKind::Down(MouseButton::Left) = mouse_event.kind {
+            if let Some(sim_pos) = self.screen_to_sim(mouse_event.column, mouse_event.row) {
+                self.select_nearest(sim_pos);
+            }
+        }
+        Ok(())
+    }
+
+    /// maps a terminal cell back into simulation coordinates using the
+    /// viewport the chart was last drawn with, or `None` if the click
+    /// landed outside the plot
+    fn screen_to_sim(&self, column: u16, row: u16) -> Option<Vec2> {
+        let area = self.plot_area;
+        if column < area.x
+            || row < area.y
+            || column >= area.x + area.width
+            || row >= area.y + area.height
+        {
+            return None;
+        }
+
+        let fx = (column - area.x) as f64 / area.width.max(1) as f64;
+        let fy = (row - area.y) as f64 / area.height.max(1) as f64;
+
+        let span = self.camera.half_extent * 2.0;
+        Some(Vec2 {
+            x: self.camera.center.x - self.camera.half_extent + fx * span,
+            // screen rows grow downward, simulation y grows upward
+            y: self.camera.center.y + self.camera.half_extent - fy * span,
+        })
+    }
+
+    /// selects the nearest body to `sim_pos` within a small pick radius, or
+    /// clears the selection if none are close enough
+    fn select_nearest(&mut self, sim_pos: Vec2) {
+        let pick_radius = self.camera.half_extent * 0.05;
+
+        self.selected = self
+            .planets
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let r = p.pos - sim_pos;
+                (i, r.dot(&r).sqrt())
+            })
+            .filter(|(_, dist)| *dist <= pick_radius)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(i, _)| i);
+    }
+
     fn exit(&mut self) {
         self.exit = true;
     }
@@ -109,7 +438,13 @@ impl Widget for &App {
             .map(|planet| (planet.pos.x, planet.pos.y))
             .collect::<Vec<_>>();
 
-        let datasets = vec![
+        let trail_positions = self
+            .trails
+            .iter()
+            .map(|trail| trail.iter().map(|p| (p.x, p.y)).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+
+        let mut datasets = vec![
             // Scatter chart
             Dataset::default()
                 .name("planets")
@@ -119,16 +454,60 @@ impl Widget for &App {
                 .data(&positions),
         ];
 
+        for trail in &trail_positions {
+            if trail.len() < 2 {
+                continue;
+            }
+            datasets.push(
+                Dataset::default()
+                    .marker(symbols::Marker::Braille)
+                    .graph_type(GraphType::Line)
+                    .style(Style::default().dark_gray())
+                    .data(trail),
+            );
+        }
+
         let title = Title::from(" Gravity ".bold());
+        let status = if self.paused { "Paused" } else { "Running" };
         let instructions = Title::from(Line::from(vec![
-            // " Decrement ".into(),
-            // "<Left>".blue().bold(),
-            // " Increment ".into(),
-            // "<Right>".blue().bold(),
+            format!(" {status} (speed x{}) ", self.speed).into(),
+            " Pause ".into(),
+            "<Space> ".blue().bold(),
+            " Step ".into(),
+            "<S> ".blue().bold(),
+            " Speed ".into(),
+            "<+/-> ".blue().bold(),
+            " Pan ".into(),
+            "<Arrows> ".blue().bold(),
+            " Zoom ".into(),
+            "<PgUp/PgDn> ".blue().bold(),
+            " Auto-fit ".into(),
+            "<A> ".blue().bold(),
+            format!(" Gravity: {} ", if self.barnes_hut { "Barnes-Hut" } else { "Exact" }).into(),
+            "<B> ".blue().bold(),
+            " Follow ".into(),
+            "<F> ".blue().bold(),
+            format!(" Trails: {} ", self.trail_length).into(),
+            "<[/]> ".blue().bold(),
+            " Clear ".into(),
+            "<C> ".blue().bold(),
             " Quit ".into(),
             "<Q> ".blue().bold(),
         ]));
-        let block = Block::bordered()
+
+        let info = self.selected.and_then(|i| self.planets.get(i)).map(|p| {
+            let speed = p.vel.dot(&p.vel).sqrt();
+            Title::from(Line::from(format!(
+                " mass {:.2}  speed {:.2}  pos ({:.2}, {:.2}) ",
+                p.mass, speed, p.pos.x, p.pos.y
+            )))
+        });
+
+        let x_min = self.camera.center.x - self.camera.half_extent;
+        let x_max = self.camera.center.x + self.camera.half_extent;
+        let y_labels = self.y_axis_labels();
+
+        let mut block = Block::bordered()
             .title(title.alignment(Alignment::Center))
             .title(
                 instructions
@@ -136,32 +515,31 @@ impl Widget for &App {
                     .position(Position::Bottom),
             )
             .border_set(border::THICK);
+        if let Some(info) = info {
+            block = block.title(info.alignment(Alignment::Right));
+        }
 
-        // Create the X axis and define its properties
+        // Axis titles stay unset: Chart reserves an extra title row/column
+        // we don't account for in `plot_area`/`y_axis_gutter_width`. Tick
+        // labels are kept, since only the y-axis labels reserve a gutter
+        // (compensated for in `render_frame`) — the x-axis labels draw
+        // inside the existing plot height.
         let x_axis = Axis::default()
-            .title("X Axis".red())
             .style(Style::default().white())
-            .bounds([-10.0, 10.0])
-            .labels(vec!["-10.0".into(), "0.0".into(), "10.0".into()]);
-
-        // Create the Y axis and define its properties
+            .bounds([x_min, x_max])
+            .labels(vec![
+                format!("{x_min:.1}").into(),
+                format!("{:.1}", self.camera.center.x).into(),
+                format!("{x_max:.1}").into(),
+            ]);
         let y_axis = Axis::default()
-            .title("Y Axis".red())
             .style(Style::default().white())
-            .bounds([-10.0, 10.0])
-            .labels(vec!["-10.0".into(), "0.0".into(), "10.0".into()]);
+            .bounds([self.camera.center.y - self.camera.half_extent, self.camera.center.y + self.camera.half_extent])
+            .labels(y_labels.into_iter().map(Line::from).collect());
 
         // Create the chart and link all the parts together
-        let chart = Chart::new(datasets)
-            .block(Block::new().title("Planets"))
-            .x_axis(x_axis)
-            .y_axis(y_axis);
-
-        // Paragraph::new(chart)
-        //     .centered()
-        //     .block(block)
-        //     .render(area, buf);
-        
+        let chart = Chart::new(datasets).x_axis(x_axis).y_axis(y_axis);
+
         let inner_area = block.inner(area);
         block.render(area, buf);
 
@@ -172,15 +550,36 @@ impl Widget for &App {
 fn main() -> Result<()> {
     errors::install_hooks()?;
     let mut terminal = tui::init()?;
+
+    let scenario = match std::env::args().nth(1) {
+        Some(path) => scenario::Scenario::load(&path)?,
+        None => scenario::Scenario::default_four_body_square(),
+    };
+
+    let (g, dt, epsilon) = (scenario.g, scenario.dt, scenario.epsilon);
+    let planets = scenario.into_planets();
+    let trails = planets.iter().map(|_| VecDeque::new()).collect();
+
     let mut app = App {
         exit: false,
         rx: events::spawn_event_threads().1,
-        planets: Vec::new(),
+        g,
+        dt,
+        epsilon,
+        planets,
+        paused: false,
+        speed: 1,
+        merge_collisions: true,
+        camera: Camera::default(),
+        auto_fit: true,
+        barnes_hut: false,
+        theta: 0.5,
+        plot_area: Rect::default(),
+        selected: None,
+        follow_selected: false,
+        trails,
+        trail_length: 200,
     };
-    app.planets.push(Planet { pos: Vec2 {x:  3.0, y:  4.0 }, vel: Vec2 { x: 0.0, y: 0.0 }});
-    app.planets.push(Planet { pos: Vec2 {x: -3.0, y:  4.0 }, vel: Vec2 { x: 0.0, y: 0.0 }});
-    app.planets.push(Planet { pos: Vec2 {x: -3.0, y: -4.0 }, vel: Vec2 { x: 0.0, y: 0.0 }});
-    app.planets.push(Planet { pos: Vec2 {x:  3.0, y: -4.0 }, vel: Vec2 { x: 0.0, y: 0.0 }});
     app.run(&mut terminal)?;
     tui::restore()?;
     Ok(())
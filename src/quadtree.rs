@@ -0,0 +1,179 @@
+use crate::Vec2;
+
+/// a single body as seen by the tree: its position and mass
+#[derive(Clone, Copy, Debug)]
+pub struct Body {
+    pub pos: Vec2,
+    pub mass: f64,
+}
+
+/// axis-aligned square region of simulation space used to build the tree
+#[derive(Clone, Copy, Debug)]
+struct Square {
+    center: Vec2,
+    half_size: f64,
+}
+
+impl Square {
+    fn quadrant(&self, which: usize) -> Square {
+        let half = self.half_size / 2.0;
+        let offset = match which {
+            0 => Vec2 { x: -half, y: half },
+            1 => Vec2 { x: half, y: half },
+            2 => Vec2 { x: -half, y: -half },
+            _ => Vec2 { x: half, y: -half },
+        };
+        Square {
+            center: self.center + offset,
+            half_size: half,
+        }
+    }
+
+    fn quadrant_of(&self, pos: Vec2) -> usize {
+        match (pos.x >= self.center.x, pos.y >= self.center.y) {
+            (false, true) => 0,
+            (true, true) => 1,
+            (false, false) => 2,
+            (true, false) => 3,
+        }
+    }
+}
+
+enum Contents {
+    Empty,
+    Leaf(Body),
+    Internal(Box<[Node; 4]>),
+}
+
+struct Node {
+    bounds: Square,
+    contents: Contents,
+    total_mass: f64,
+    center_of_mass: Vec2,
+}
+
+impl Node {
+    fn new(bounds: Square) -> Node {
+        Node {
+            bounds,
+            contents: Contents::Empty,
+            total_mass: 0.0,
+            center_of_mass: Vec2::default(),
+        }
+    }
+
+    fn insert(&mut self, body: Body) {
+        if self.total_mass + body.mass > 0.0 {
+            self.center_of_mass = (self.center_of_mass * self.total_mass + body.pos * body.mass)
+                / (self.total_mass + body.mass);
+        }
+        self.total_mass += body.mass;
+
+        match &mut self.contents {
+            Contents::Empty => self.contents = Contents::Leaf(body),
+            Contents::Leaf(existing) => {
+                let existing = *existing;
+
+                // Bodies exactly on top of each other would recurse into the
+                // same quadrant forever, subdividing down to the smallest
+                // representable square. Fold them into one aggregate point
+                // mass instead; a body at that same position already skips
+                // interacting with it via the dist2 == 0.0 guard below, the
+                // same way the exact solver treats self-interaction.
+                if existing.pos.x == body.pos.x && existing.pos.y == body.pos.y {
+                    self.contents = Contents::Leaf(Body {
+                        pos: existing.pos,
+                        mass: existing.mass + body.mass,
+                    });
+                    return;
+                }
+
+                let mut children = [
+                    Node::new(self.bounds.quadrant(0)),
+                    Node::new(self.bounds.quadrant(1)),
+                    Node::new(self.bounds.quadrant(2)),
+                    Node::new(self.bounds.quadrant(3)),
+                ];
+                children[self.bounds.quadrant_of(existing.pos)].insert(existing);
+                children[self.bounds.quadrant_of(body.pos)].insert(body);
+                self.contents = Contents::Internal(Box::new(children));
+            }
+            Contents::Internal(children) => {
+                children[self.bounds.quadrant_of(body.pos)].insert(body);
+            }
+        }
+    }
+
+    /// accumulates the acceleration this node exerts on a body at `at` into
+    /// `acc`, treating any node whose width/distance ratio is below `theta`
+    /// as a single point mass at its center of mass
+    fn accumulate(&self, at: Vec2, g: f64, eps2: f64, theta: f64, acc: &mut Vec2) {
+        match &self.contents {
+            Contents::Empty => {}
+            Contents::Leaf(body) => *acc += pairwise_acceleration(at, body.pos, body.mass, g, eps2),
+            Contents::Internal(children) => {
+                let r = self.center_of_mass - at;
+                let dist = r.dot(&r).sqrt();
+                let width = self.bounds.half_size * 2.0;
+
+                if dist > 0.0 && width / dist < theta {
+                    *acc += pairwise_acceleration(at, self.center_of_mass, self.total_mass, g, eps2);
+                } else {
+                    for child in children.iter() {
+                        child.accumulate(at, g, eps2, theta, acc);
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn pairwise_acceleration(at: Vec2, other_pos: Vec2, other_mass: f64, g: f64, eps2: f64) -> Vec2 {
+    let r = other_pos - at;
+    let dist2 = r.dot(&r);
+    if dist2 == 0.0 {
+        return Vec2::default();
+    }
+    let inv_dist3 = (dist2 + eps2).powf(-1.5);
+    r * (g * other_mass * inv_dist3)
+}
+
+/// computes the acceleration on every body in `bodies` using the Barnes–Hut
+/// approximation: a quadtree is built over their bounding square, then each
+/// body walks the tree, treating distant nodes as a single mass whenever
+/// `node_width / distance < theta`
+pub fn accelerations(bodies: &[Body], g: f64, eps2: f64, theta: f64) -> Vec<Vec2> {
+    let Some(first) = bodies.first() else {
+        return Vec::new();
+    };
+
+    let mut min = first.pos;
+    let mut max = first.pos;
+    for b in &bodies[1..] {
+        min.x = min.x.min(b.pos.x);
+        min.y = min.y.min(b.pos.y);
+        max.x = max.x.max(b.pos.x);
+        max.y = max.y.max(b.pos.y);
+    }
+
+    let center = Vec2 {
+        x: (min.x + max.x) / 2.0,
+        y: (min.y + max.y) / 2.0,
+    };
+    // pad slightly so bodies on the boundary still fall strictly inside a quadrant
+    let half_size = ((max.x - min.x).max(max.y - min.y) / 2.0).max(1.0) * 1.01;
+
+    let mut root = Node::new(Square { center, half_size });
+    for &body in bodies {
+        root.insert(body);
+    }
+
+    bodies
+        .iter()
+        .map(|b| {
+            let mut acc = Vec2::default();
+            root.accumulate(b.pos, g, eps2, theta, &mut acc);
+            acc
+        })
+        .collect()
+}
@@ -0,0 +1,88 @@
+use color_eyre::{eyre::WrapErr, Result};
+use serde::Deserialize;
+
+use crate::{Planet, Vec2};
+
+/// top-level shape of a scenario TOML file: simulation constants plus a
+/// list of bodies to seed `App::planets` with
+#[derive(Debug, Deserialize)]
+pub struct Scenario {
+    #[serde(default = "default_g")]
+    pub g: f64,
+    #[serde(default = "default_dt")]
+    pub dt: f64,
+    #[serde(default = "default_epsilon")]
+    pub epsilon: f64,
+    pub bodies: Vec<BodyConfig>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BodyConfig {
+    pub pos: [f64; 2],
+    #[serde(default)]
+    pub vel: [f64; 2],
+    pub mass: f64,
+    // parsed but not consumed: neither the info readout nor the trails
+    // show a body's name or color
+    #[allow(dead_code)]
+    pub name: Option<String>,
+    #[allow(dead_code)]
+    pub color: Option<String>,
+}
+
+fn default_g() -> f64 {
+    1.0
+}
+
+fn default_dt() -> f64 {
+    0.01
+}
+
+fn default_epsilon() -> f64 {
+    0.1
+}
+
+impl Scenario {
+    /// the built-in four-body square used when no scenario file is given
+    pub fn default_four_body_square() -> Scenario {
+        Scenario {
+            g: default_g(),
+            dt: default_dt(),
+            epsilon: default_epsilon(),
+            bodies: vec![
+                body(3.0, 4.0),
+                body(-3.0, 4.0),
+                body(-3.0, -4.0),
+                body(3.0, -4.0),
+            ],
+        }
+    }
+
+    /// reads and parses a scenario from a TOML file at `path`
+    pub fn load(path: &str) -> Result<Scenario> {
+        let contents = std::fs::read_to_string(path)
+            .wrap_err_with(|| format!("reading scenario file {path}"))?;
+        toml::from_str(&contents).wrap_err_with(|| format!("parsing scenario file {path}"))
+    }
+
+    pub fn into_planets(self) -> Vec<Planet> {
+        self.bodies
+            .into_iter()
+            .map(|b| Planet {
+                pos: Vec2 { x: b.pos[0], y: b.pos[1] },
+                vel: Vec2 { x: b.vel[0], y: b.vel[1] },
+                mass: b.mass,
+            })
+            .collect()
+    }
+}
+
+fn body(x: f64, y: f64) -> BodyConfig {
+    BodyConfig {
+        pos: [x, y],
+        vel: [0.0, 0.0],
+        mass: 1.0,
+        name: None,
+        color: None,
+    }
+}
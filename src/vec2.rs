@@ -10,14 +10,6 @@ impl Vec2 {
     pub fn dot(&self, other: &Vec2) -> f64 {
         self.x * other.x + self.y * other.y
     }
-
-    pub fn normalized(&self) -> Vec2 {
-        let length = (self.x * self.x + self.y * self.y).sqrt();
-        Vec2 {
-            x: self.x / length,
-            y: self.y / length,
-        }
-    }
 }
 
 impl ops::Add<Self> for Vec2 {